@@ -46,6 +46,9 @@ pub struct GraphNode {
     parents: Vec<String>,
     is_root: bool,
     is_end: bool,
+    // "word" or "phrase" — phrase nodes additionally carry their ordered token list.
+    kind: String,
+    tokens: Option<Vec<String>>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -63,11 +66,177 @@ pub struct GraphData {
     unique_words: u32,
 }
 
+// A boolean query predicate over the tokenized corpus, e.g. `("model" AND "weights") OR "training"`.
+#[derive(Debug, Clone, PartialEq)]
+enum Operation {
+    And(Vec<Operation>),
+    Or(Vec<Operation>),
+    Phrase(Vec<String>),
+    Term(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum QueryToken {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Literal(String),
+}
+
+fn tokenize_query(expr: &str) -> Result<Vec<QueryToken>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = expr.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(QueryToken::LParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(QueryToken::RParen);
+            }
+            '"' => {
+                chars.next();
+                let mut literal = String::new();
+                let mut closed = false;
+                for c in chars.by_ref() {
+                    if c == '"' {
+                        closed = true;
+                        break;
+                    }
+                    literal.push(c);
+                }
+                if !closed {
+                    return Err("unterminated phrase literal".to_string());
+                }
+                tokens.push(QueryToken::Literal(literal));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                match word.as_str() {
+                    "AND" => tokens.push(QueryToken::And),
+                    "OR" => tokens.push(QueryToken::Or),
+                    _ => return Err(format!("unexpected token '{}': terms must be quoted", word)),
+                }
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+// Recursive-descent parser: expr := and_expr (OR and_expr)*, and_expr := primary (AND primary)*,
+// primary := '(' expr ')' | quoted literal.
+struct QueryParser {
+    tokens: Vec<QueryToken>,
+    pos: usize,
+}
+
+impl QueryParser {
+    fn new(tokens: Vec<QueryToken>) -> Self {
+        QueryParser { tokens, pos: 0 }
+    }
+
+    fn peek(&self) -> Option<&QueryToken> {
+        self.tokens.get(self.pos)
+    }
+
+    fn advance(&mut self) -> Option<QueryToken> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn parse_expr(&mut self) -> Result<Operation, String> {
+        let mut terms = vec![self.parse_and()?];
+        while matches!(self.peek(), Some(QueryToken::Or)) {
+            self.advance();
+            terms.push(self.parse_and()?);
+        }
+        Ok(if terms.len() == 1 { terms.remove(0) } else { Operation::Or(terms) })
+    }
+
+    fn parse_and(&mut self) -> Result<Operation, String> {
+        let mut terms = vec![self.parse_primary()?];
+        while matches!(self.peek(), Some(QueryToken::And)) {
+            self.advance();
+            terms.push(self.parse_primary()?);
+        }
+        Ok(if terms.len() == 1 { terms.remove(0) } else { Operation::And(terms) })
+    }
+
+    fn parse_primary(&mut self) -> Result<Operation, String> {
+        match self.advance() {
+            Some(QueryToken::LParen) => {
+                let expr = self.parse_expr()?;
+                match self.advance() {
+                    Some(QueryToken::RParen) => Ok(expr),
+                    other => Err(format!("expected ')', found {:?}", other)),
+                }
+            }
+            Some(QueryToken::Literal(text)) => {
+                let words: Vec<String> = text
+                    .split_whitespace()
+                    .map(|w| w.to_lowercase())
+                    .collect();
+                if words.is_empty() {
+                    return Err("empty quoted literal".to_string());
+                }
+                Ok(if words.len() > 1 {
+                    Operation::Phrase(words)
+                } else {
+                    Operation::Term(words.into_iter().next().unwrap_or_default())
+                })
+            }
+            other => Err(format!("expected a quoted term or '(', found {:?}", other)),
+        }
+    }
+}
+
+fn parse_query(expr: &str) -> Result<Operation, String> {
+    let tokens = tokenize_query(expr)?;
+    let mut parser = QueryParser::new(tokens);
+    let operation = parser.parse_expr()?;
+    if parser.pos != parser.tokens.len() {
+        return Err("unexpected trailing tokens after a complete expression".to_string());
+    }
+    Ok(operation)
+}
+
 #[wasm_bindgen]
 pub struct TextProcessor {
     word_cache: HashMap<String, WordMetadata>,
     stop_words: HashSet<String>,
     generation_count: usize,
+    // Surviving (non-stop, length>2) tokens per generation, in positional order,
+    // kept around so graph builders can reconstruct sequence rather than just co-occurrence.
+    generation_tokens: Vec<Vec<String>>,
+    // Parallel to generation_tokens: each surviving token's position in the full (stop words
+    // included) per-generation token stream -- the same index space WordMetadata.word_indices
+    // uses -- so phrase occurrences can be reported in that same space.
+    generation_token_positions: Vec<Vec<usize>>,
+    // Memoized fuzzy-match derivations for a (word, max_typo) pair, so repeated
+    // merge_variants calls don't redo the bounded Levenshtein search for unchanged words.
+    fuzzy_derivations_cache: HashMap<(String, usize), Vec<String>>,
+    // User-supplied variant -> canonical mapping ("usa" -> "united states"), applied before
+    // a token enters word_cache.
+    synonyms: HashMap<String, String>,
+    // When enabled, tokenization also tries joining adjacent short tokens and splitting long
+    // tokens against the observed vocabulary before canonicalization.
+    auto_split_concat: bool,
 }
 
 #[wasm_bindgen]
@@ -88,6 +257,11 @@ impl TextProcessor {
             word_cache: HashMap::new(),
             stop_words,
             generation_count: 0,
+            generation_tokens: Vec::new(),
+            generation_token_positions: Vec::new(),
+            fuzzy_derivations_cache: HashMap::new(),
+            synonyms: HashMap::new(),
+            auto_split_concat: false,
         }
     }
 
@@ -110,10 +284,28 @@ impl TextProcessor {
         
         // Clear previous cache
         self.word_cache.clear();
-        
+        self.generation_tokens.clear();
+        self.generation_token_positions.clear();
+        self.fuzzy_derivations_cache.clear();
+
+        // Tokenize every generation up front so split/concat normalization can match
+        // candidate joins/splits against the full observed vocabulary, not just what's been
+        // seen in earlier generations.
+        let tokenized_generations: Vec<Vec<String>> = generations_vec.iter()
+            .map(|generation| self.tokenize_sentence(generation))
+            .collect();
+
+        let vocabulary: HashSet<&str> = if self.auto_split_concat {
+            tokenized_generations.iter()
+                .flat_map(|tokens| tokens.iter().map(|token| token.as_str()))
+                .collect()
+        } else {
+            HashSet::new()
+        };
+
         // Process each generation
-        for (sent_idx, generation) in generations_vec.iter().enumerate() {
-            self.process_sentence(generation, sent_idx);
+        for (sent_idx, tokens) in tokenized_generations.iter().enumerate() {
+            self.process_sentence(tokens, sent_idx, &vocabulary);
         }
 
         let processing_time = js_sys::Date::now() - start_time;
@@ -148,24 +340,38 @@ impl TextProcessor {
     #[wasm_bindgen]
     pub fn build_word_graph(&self, min_frequency: u32) -> Result<JsValue, JsValue> {
         let start_time = js_sys::Date::now();
-        
+
         console_log!("🔗 Building word graph with min frequency: {}", min_frequency);
-        
+
         // Filter words by frequency
-        let filtered_words: Vec<_> = self.word_cache.iter()
+        let filtered_words: Vec<(&String, &WordMetadata)> = self.word_cache.iter()
             .filter(|(_, metadata)| metadata.count >= min_frequency)
             .collect();
-            
+
         console_log!("📊 Filtered to {} words (freq >= {})", filtered_words.len(), min_frequency);
-        
-        let mut nodes = Vec::new();
-        let mut links = Vec::new();
-        let mut word_to_index = HashMap::new();
-        
-        // Create nodes
-        for (idx, (word, metadata)) in filtered_words.iter().enumerate() {
-            word_to_index.insert(word.clone(), idx);
-            
+
+        // Intern each filtered word into a small integer id so co-occurrence accumulation
+        // works over packed ids instead of formatted string keys.
+        let mut word_by_id: Vec<&String> = Vec::with_capacity(filtered_words.len());
+        let mut id_by_word: HashMap<&str, u32> = HashMap::with_capacity(filtered_words.len());
+        for (word, _) in &filtered_words {
+            id_by_word.insert(word.as_str(), word_by_id.len() as u32);
+            word_by_id.push(word);
+        }
+
+        // One bit per generation index, per interned word. Intersection size (link weight)
+        // then becomes a bitwise AND + popcount instead of a Vec::contains scan.
+        let bitset_words = ((self.generation_count + 63) / 64).max(1);
+        let mut sentence_bitsets: Vec<Vec<u64>> = vec![vec![0u64; bitset_words]; word_by_id.len()];
+        for (word, metadata) in &filtered_words {
+            let id = id_by_word[word.as_str()] as usize;
+            for &sentence in &metadata.sentences {
+                sentence_bitsets[id][sentence / 64] |= 1u64 << (sentence % 64);
+            }
+        }
+
+        let mut nodes = Vec::with_capacity(word_by_id.len());
+        for (word, metadata) in &filtered_words {
             nodes.push(GraphNode {
                 word: word.to_string(),
                 count: metadata.count,
@@ -175,60 +381,504 @@ impl TextProcessor {
                 parents: Vec::new(),
                 is_root: false,
                 is_end: false,
+                kind: "word".to_string(),
+                tokens: None,
             });
         }
-        
-        // Calculate co-occurrence links
-        let mut co_occurrence = HashMap::new();
-        
-        // For each pair of words, check if they appear in the same sentences
-        for (word1, meta1) in &filtered_words {
-            for (word2, meta2) in &filtered_words {
-                if word1 != word2 {
-                    let common_sentences: HashSet<_> = meta1.sentences.iter()
-                        .filter(|s| meta2.sentences.contains(s))
-                        .collect();
-                    
-                    if !common_sentences.is_empty() {
-                        let key = if word1 < word2 { 
-                            format!("{}|{}", word1, word2) 
-                        } else { 
-                            format!("{}|{}", word2, word1) 
-                        };
-                        co_occurrence.insert(key, common_sentences.len() as u32);
-                    }
+
+        // Per-generation accumulation: a pair is only ever considered when both words
+        // actually occur in that generation, so this is O(generations x words_per_generation^2)
+        // instead of the previous O(words^2 x sentences).
+        let mut co_occurring_pairs: HashSet<(u32, u32)> = HashSet::new();
+        for tokens in &self.generation_tokens {
+            let mut present: Vec<u32> = tokens.iter()
+                .filter_map(|word| id_by_word.get(word.as_str()).copied())
+                .collect();
+            present.sort_unstable();
+            present.dedup();
+
+            for i in 0..present.len() {
+                for j in (i + 1)..present.len() {
+                    co_occurring_pairs.insert((present[i], present[j]));
                 }
             }
         }
-        
-        // Create links from co-occurrence data
-        for (key, weight) in co_occurrence {
-            let parts: Vec<&str> = key.split('|').collect();
-            if parts.len() == 2 {
-                links.push(GraphLink {
-                    source: parts[0].to_string(),
-                    target: parts[1].to_string(),
-                    weight,
-                });
-            }
+
+        let mut links = Vec::with_capacity(co_occurring_pairs.len());
+        for (id1, id2) in co_occurring_pairs {
+            let weight: u32 = sentence_bitsets[id1 as usize].iter()
+                .zip(&sentence_bitsets[id2 as usize])
+                .map(|(a, b)| (a & b).count_ones())
+                .sum();
+
+            links.push(GraphLink {
+                source: word_by_id[id1 as usize].to_string(),
+                target: word_by_id[id2 as usize].to_string(),
+                weight,
+            });
         }
-        
+
         let graph_data = GraphData {
             nodes,
             links,
             total_words: self.word_cache.values().map(|m| m.count).sum(),
             unique_words: self.word_cache.len() as u32,
         };
-        
+
         let processing_time = js_sys::Date::now() - start_time;
-        console_log!("⚡ Graph built in {:.2}ms: {} nodes, {} links", 
+        console_log!("⚡ Graph built in {:.2}ms: {} nodes, {} links",
                     processing_time, graph_data.nodes.len(), graph_data.links.len());
-        
+
         // Convert to JS
         let result = serde_wasm_bindgen::to_value(&graph_data)?;
         Ok(result)
     }
 
+    // Builds a directed graph of token transitions (prev -> next) observed within each
+    // generation, instead of undirected "same sentence" co-occurrence. This surfaces the
+    // branching/merging structure of how generations diverge and reconverge.
+    #[wasm_bindgen]
+    pub fn build_transition_graph(&self, min_frequency: u32) -> Result<JsValue, JsValue> {
+        let start_time = js_sys::Date::now();
+
+        console_log!("🔀 Building transition graph with min frequency: {}", min_frequency);
+
+        let filtered_words: HashMap<&String, &WordMetadata> = self.word_cache.iter()
+            .filter(|(_, metadata)| metadata.count >= min_frequency)
+            .collect();
+
+        console_log!("📊 Filtered to {} words (freq >= {})", filtered_words.len(), min_frequency);
+
+        let mut nodes: HashMap<String, GraphNode> = HashMap::new();
+        for (word, metadata) in &filtered_words {
+            nodes.insert((*word).clone(), GraphNode {
+                word: (*word).clone(),
+                count: metadata.count,
+                sentences: metadata.sentences.clone(),
+                word_indices: metadata.word_indices.clone(),
+                children: Vec::new(),
+                parents: Vec::new(),
+                is_root: false,
+                is_end: false,
+                kind: "word".to_string(),
+                tokens: None,
+            });
+        }
+
+        // Accumulate edge weight as the number of generations a transition occurs in,
+        // not the raw occurrence count, so a repeated transition within one generation
+        // only contributes once.
+        let mut edge_weights: HashMap<(String, String), u32> = HashMap::new();
+
+        // is_root/is_end and adjacency are computed over the full (unfiltered) surviving
+        // sequence, so a low-frequency token filtered out of the output never fabricates a
+        // phantom "first" token or a phantom adjacency between tokens that weren't actually
+        // next to each other. min_frequency only decides which resulting nodes/edges survive.
+        for tokens in &self.generation_tokens {
+            if tokens.is_empty() {
+                continue;
+            }
+
+            if let Some(node) = nodes.get_mut(&tokens[0]) {
+                node.is_root = true;
+            }
+            if let Some(node) = nodes.get_mut(&tokens[tokens.len() - 1]) {
+                node.is_end = true;
+            }
+
+            let mut seen_transitions = HashSet::new();
+            for pair in tokens.windows(2) {
+                let (prev, next) = (&pair[0], &pair[1]);
+                if !filtered_words.contains_key(prev) || !filtered_words.contains_key(next) {
+                    continue;
+                }
+                if seen_transitions.insert((prev.clone(), next.clone())) {
+                    *edge_weights.entry((prev.clone(), next.clone())).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let mut links = Vec::new();
+        for ((source, target), weight) in edge_weights {
+            if let Some(node) = nodes.get_mut(&source) {
+                node.children.push(target.clone());
+            }
+            if let Some(node) = nodes.get_mut(&target) {
+                node.parents.push(source.clone());
+            }
+            links.push(GraphLink { source, target, weight });
+        }
+
+        let graph_data = GraphData {
+            nodes: nodes.into_values().collect(),
+            links,
+            total_words: self.word_cache.values().map(|m| m.count).sum(),
+            unique_words: self.word_cache.len() as u32,
+        };
+
+        let processing_time = js_sys::Date::now() - start_time;
+        console_log!("⚡ Transition graph built in {:.2}ms: {} nodes, {} links",
+                    processing_time, graph_data.nodes.len(), graph_data.links.len());
+
+        let result = serde_wasm_bindgen::to_value(&graph_data)?;
+        Ok(result)
+    }
+
+    // Promotes recurring contiguous n-grams (n = 2..=max_phrase_len) that meet
+    // min_phrase_frequency into phrase nodes, so a canned multi-word span shows up as one
+    // node instead of being scattered across its constituent single-word nodes. To avoid
+    // double counting, a word node's count is reduced by its occurrences absorbed into a
+    // promoted phrase, and a word node whose every occurrence is absorbed is dropped.
+    #[wasm_bindgen]
+    pub fn build_phrase_graph(&self, min_word_frequency: u32, max_phrase_len: usize, min_phrase_frequency: u32) -> Result<JsValue, JsValue> {
+        let start_time = js_sys::Date::now();
+
+        console_log!("🧩 Building phrase graph (min_word_frequency={}, max_phrase_len={}, min_phrase_frequency={})",
+                    min_word_frequency, max_phrase_len, min_phrase_frequency);
+
+        // phrase tokens -> (generations it appears in, raw occurrence count, (sent_idx, start) of each occurrence)
+        let mut phrase_stats: HashMap<Vec<String>, (HashSet<usize>, u32, Vec<(usize, usize)>)> = HashMap::new();
+
+        for (sent_idx, tokens) in self.generation_tokens.iter().enumerate() {
+            for n in 2..=max_phrase_len.max(2) {
+                if tokens.len() < n {
+                    continue;
+                }
+                for start in 0..=(tokens.len() - n) {
+                    let ngram = tokens[start..start + n].to_vec();
+                    let entry = phrase_stats.entry(ngram)
+                        .or_insert_with(|| (HashSet::new(), 0, Vec::new()));
+                    entry.0.insert(sent_idx);
+                    entry.1 += 1;
+                    entry.2.push((sent_idx, start));
+                }
+            }
+        }
+
+        let mut phrase_nodes = Vec::new();
+        // Every (generation, token position) consumed by some promoted phrase, so a word
+        // covered by more than one overlapping phrase ("model" in both "language model" and
+        // "model weights") only ever has each real occurrence subtracted once.
+        let mut consumed_positions: HashSet<(usize, usize)> = HashSet::new();
+
+        for (tokens, (sentences, occurrences, occurrence_positions)) in phrase_stats {
+            if sentences.len() as u32 >= min_phrase_frequency {
+                let mut sorted_sentences: Vec<usize> = sentences.into_iter().collect();
+                sorted_sentences.sort_unstable();
+
+                for &(sent_idx, start) in &occurrence_positions {
+                    for offset in 0..tokens.len() {
+                        consumed_positions.insert((sent_idx, start + offset));
+                    }
+                }
+
+                // Report each occurrence's start in the same index space WordMetadata.word_indices
+                // already uses for word nodes -- the full (stop words included) per-generation
+                // token stream -- not a position in the filtered survivor-only stream.
+                let word_indices: Vec<usize> = occurrence_positions.iter()
+                    .map(|&(sent_idx, start)| {
+                        self.generation_token_positions.get(sent_idx)
+                            .and_then(|positions| positions.get(start))
+                            .copied()
+                            .unwrap_or(start)
+                    })
+                    .collect();
+
+                phrase_nodes.push(GraphNode {
+                    word: tokens.join(" "),
+                    count: occurrences,
+                    sentences: sorted_sentences,
+                    word_indices,
+                    children: Vec::new(),
+                    parents: Vec::new(),
+                    is_root: false,
+                    is_end: false,
+                    kind: "phrase".to_string(),
+                    tokens: Some(tokens),
+                });
+            }
+        }
+
+        console_log!("🧩 Promoted {} phrase(s) (n=2..={})", phrase_nodes.len(), max_phrase_len);
+
+        // Count how many consumed positions belong to each word, so a word's count is only
+        // down-weighted by occurrences genuinely absorbed into a promoted phrase.
+        let mut absorbed_by_word: HashMap<&str, u32> = HashMap::new();
+        for &(sent_idx, position) in &consumed_positions {
+            if let Some(word) = self.generation_tokens.get(sent_idx).and_then(|tokens| tokens.get(position)) {
+                *absorbed_by_word.entry(word.as_str()).or_insert(0) += 1;
+            }
+        }
+
+        // Word nodes, down-weighted by the occurrences absorbed into a promoted phrase they're
+        // a member of, dropped entirely once fully absorbed.
+        let mut word_nodes = Vec::new();
+        for (word, metadata) in &self.word_cache {
+            if metadata.count < min_word_frequency {
+                continue;
+            }
+
+            let absorbed = absorbed_by_word.get(word.as_str()).copied().unwrap_or(0);
+            let count = metadata.count.saturating_sub(absorbed);
+
+            if count == 0 {
+                continue;
+            }
+
+            word_nodes.push(GraphNode {
+                word: word.clone(),
+                count,
+                sentences: metadata.sentences.clone(),
+                word_indices: metadata.word_indices.clone(),
+                children: Vec::new(),
+                parents: Vec::new(),
+                is_root: false,
+                is_end: false,
+                kind: "word".to_string(),
+                tokens: None,
+            });
+        }
+
+        let mut nodes = word_nodes;
+        nodes.extend(phrase_nodes);
+
+        let graph_data = GraphData {
+            nodes,
+            links: Vec::new(),
+            total_words: self.word_cache.values().map(|m| m.count).sum(),
+            unique_words: self.word_cache.len() as u32,
+        };
+
+        let processing_time = js_sys::Date::now() - start_time;
+        console_log!("⚡ Phrase graph built in {:.2}ms: {} nodes", processing_time, graph_data.nodes.len());
+
+        let result = serde_wasm_bindgen::to_value(&graph_data)?;
+        Ok(result)
+    }
+
+    // Loads a variant -> canonical synonym table (e.g. {"usa": "united states"}) applied
+    // during the next tokenize_generations call. Keys and values are lowercased to match the
+    // tokenizer's own lowercasing.
+    #[wasm_bindgen]
+    pub fn set_synonyms(&mut self, synonyms: &JsValue) -> Result<(), JsValue> {
+        let object: Object = synonyms.clone().into();
+        let mut map = HashMap::new();
+
+        for entry in Object::entries(&object).iter() {
+            let pair: Array = entry.into();
+            let variant = pair.get(0).as_string()
+                .ok_or_else(|| JsValue::from_str("synonym key must be a string"))?;
+            let canonical = pair.get(1).as_string()
+                .ok_or_else(|| JsValue::from_str("synonym value must be a string"))?;
+            map.insert(variant.to_lowercase(), canonical.to_lowercase());
+        }
+
+        console_log!("🔤 Loaded {} synonym mapping(s)", map.len());
+        self.synonyms = map;
+        Ok(())
+    }
+
+    // Enables/disables auto split/concat normalization ("health care" <-> "healthcare")
+    // applied during the next tokenize_generations call.
+    #[wasm_bindgen]
+    pub fn set_auto_split_concat(&mut self, enabled: bool) {
+        console_log!("🔤 auto split/concat normalization {}", if enabled { "enabled" } else { "disabled" });
+        self.auto_split_concat = enabled;
+    }
+
+    // Opt-in fuzzy merge: collapses words within a bounded, length-scaled edit distance
+    // ("color"/"colour", typos) into a single canonical WordMetadata entry so near-duplicate
+    // surface forms don't fragment the consistency graph. Returns the number of words folded
+    // into another entry.
+    #[wasm_bindgen]
+    pub fn merge_variants(&mut self, max_typo: usize) -> u32 {
+        let start_time = js_sys::Date::now();
+
+        // Sort into a fixed order so pair discovery below doesn't depend on word_cache's
+        // (randomized, per-process) HashMap iteration order -- otherwise which of two
+        // matching words gets to be the lower-indexed "search initiator" is arbitrary, and
+        // the whole merge outcome becomes non-deterministic across runs of the same corpus.
+        let mut words: Vec<String> = self.word_cache.keys().cloned().collect();
+        words.sort_unstable();
+
+        let word_index: HashMap<&str, usize> = words.iter()
+            .enumerate()
+            .map(|(idx, word)| (word.as_str(), idx))
+            .collect();
+
+        let mut by_length: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (idx, word) in words.iter().enumerate() {
+            by_length.entry(word.chars().count()).or_insert_with(Vec::new).push(idx);
+        }
+
+        // The widest distance allowed_typo_distance can ever return, regardless of which
+        // word's length decides it -- used as the candidate search radius so every word
+        // searches, not only ones whose own length alone would allow a typo.
+        let max_possible_allowed = max_typo.min(2);
+
+        let mut uf = UnionFind::new(words.len());
+
+        for (idx, word) in words.iter().enumerate() {
+            if max_possible_allowed == 0 {
+                continue;
+            }
+
+            let len = word.chars().count();
+            let prefix = word.chars().next();
+            let cache_key = (word.clone(), max_typo);
+
+            let derivations = if let Some(cached) = self.fuzzy_derivations_cache.get(&cache_key) {
+                cached.clone()
+            } else {
+                let mut matches = Vec::new();
+                let mut candidate_lengths: Vec<usize> = (0..=max_possible_allowed)
+                    .flat_map(|delta| [Some(len + delta), len.checked_sub(delta)])
+                    .flatten()
+                    .collect();
+                candidate_lengths.sort_unstable();
+                candidate_lengths.dedup();
+
+                for candidate_len in candidate_lengths {
+                    let Some(candidates) = by_length.get(&candidate_len) else { continue };
+                    for &other_idx in candidates {
+                        if other_idx <= idx {
+                            continue;
+                        }
+                        let other = &words[other_idx];
+                        if other.chars().next() != prefix {
+                            continue;
+                        }
+                        // Decide the allowed distance from whichever of the two words is
+                        // longer, not just the searching word's own length, so a pair like a
+                        // 4-char word and its 5-char typo neighbor is judged the same way no
+                        // matter which one happens to search first.
+                        let pair_allowed = allowed_typo_distance(len.max(candidate_len), max_typo);
+                        if pair_allowed == 0 {
+                            continue;
+                        }
+                        if levenshtein_bounded(word, other, pair_allowed).is_some() {
+                            matches.push(other.clone());
+                        }
+                    }
+                }
+
+                self.fuzzy_derivations_cache.insert(cache_key, matches.clone());
+                matches
+            };
+
+            for other in &derivations {
+                if let Some(&other_idx) = word_index.get(other.as_str()) {
+                    uf.union(idx, other_idx);
+                }
+            }
+        }
+
+        let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+        for idx in 0..words.len() {
+            let root = uf.find(idx);
+            groups.entry(root).or_insert_with(Vec::new).push(idx);
+        }
+
+        let mut merged_count = 0u32;
+        let mut new_cache: HashMap<String, WordMetadata> = HashMap::new();
+        // variant word -> canonical word, so generation_tokens (read directly by
+        // build_transition_graph, build_phrase_graph, and query's phrase matching) can be
+        // rewritten to match the merged word_cache.
+        let mut canonical_by_word: HashMap<String, String> = HashMap::new();
+
+        for indices in groups.values() {
+            if indices.len() == 1 {
+                let word = &words[indices[0]];
+                new_cache.insert(word.clone(), self.word_cache[word].clone());
+                continue;
+            }
+
+            let canonical_idx = *indices.iter()
+                .max_by_key(|&&i| self.word_cache[&words[i]].count)
+                .unwrap();
+            let canonical_word = words[canonical_idx].clone();
+
+            let mut merged = self.word_cache[&canonical_word].clone();
+            merged.word = canonical_word.clone();
+
+            for &idx in indices {
+                if idx == canonical_idx {
+                    continue;
+                }
+                let variant_word = words[idx].clone();
+                let variant = &self.word_cache[&variant_word];
+                merged.count += variant.count;
+                for &sentence in &variant.sentences {
+                    if !merged.sentences.contains(&sentence) {
+                        merged.sentences.push(sentence);
+                    }
+                }
+                merged.word_indices.extend(variant.word_indices.iter().copied());
+                canonical_by_word.insert(variant_word, canonical_word.clone());
+                merged_count += 1;
+            }
+
+            new_cache.insert(canonical_word, merged);
+        }
+
+        self.word_cache = new_cache;
+
+        // Rewrite the shared per-generation sequence in place so every sequence-dependent
+        // consumer sees the canonical spelling too, not just word_cache.
+        if !canonical_by_word.is_empty() {
+            for tokens in &mut self.generation_tokens {
+                for token in tokens.iter_mut() {
+                    if let Some(canonical) = canonical_by_word.get(token) {
+                        *token = canonical.clone();
+                    }
+                }
+            }
+        }
+
+        let processing_time = js_sys::Date::now() - start_time;
+        console_log!("🧹 merge_variants folded {} variant(s) in {:.2}ms ({} unique words remain)",
+                    merged_count, processing_time, self.word_cache.len());
+
+        merged_count
+    }
+
+    // Evaluates a boolean query (AND/OR/phrase/term) over the tokenized corpus and returns,
+    // per matching generation, how many times it matched and where, so the front end can
+    // highlight the hits.
+    #[wasm_bindgen]
+    pub fn query(&self, expr: &str) -> Result<JsValue, JsValue> {
+        let start_time = js_sys::Date::now();
+
+        let operation = parse_query(expr).map_err(|err| JsValue::from_str(&err))?;
+        let matching_generations = self.eval_operation(&operation);
+
+        let mut sorted_matches: Vec<usize> = matching_generations.into_iter().collect();
+        sorted_matches.sort_unstable();
+
+        let results_array = Array::new();
+        for &sent_idx in &sorted_matches {
+            let positions = self.match_positions(&operation, sent_idx);
+
+            let result_obj = Object::new();
+            Reflect::set(&result_obj, &"generationIndex".into(), &(sent_idx as u32).into())?;
+            Reflect::set(&result_obj, &"matchCount".into(), &(positions.len() as u32).into())?;
+
+            let positions_array: Array = positions.iter().map(|&p| (p as u32).into()).collect();
+            Reflect::set(&result_obj, &"positions".into(), &positions_array.into())?;
+
+            results_array.push(&result_obj);
+        }
+
+        let result = Object::new();
+        Reflect::set(&result, &"matches".into(), &results_array.into())?;
+        Reflect::set(&result, &"totalMatches".into(), &(sorted_matches.len() as u32).into())?;
+
+        let processing_time = js_sys::Date::now() - start_time;
+        console_log!("🔍 Query '{}' matched {} generation(s) in {:.2}ms", expr, sorted_matches.len(), processing_time);
+
+        Ok(result.into())
+    }
+
     #[wasm_bindgen]
     pub fn get_word_frequencies(&self) -> JsValue {
         let frequencies = Object::new();
@@ -252,9 +902,11 @@ impl TextProcessor {
     }
 
     // Private helper methods
-    fn process_sentence(&mut self, sentence: &str, sent_idx: usize) {
-        let words = self.tokenize_sentence(sentence);
-        
+    fn process_sentence(&mut self, tokens: &[String], sent_idx: usize, vocabulary: &HashSet<&str>) {
+        let words = self.normalize_tokens(tokens, vocabulary);
+        let mut surviving = Vec::new();
+        let mut surviving_positions = Vec::new();
+
         for (word_idx, word) in words.iter().enumerate() {
             if !self.stop_words.contains(word) && word.len() > 2 {
                 let entry = self.word_cache.entry(word.clone()).or_insert_with(|| {
@@ -265,14 +917,20 @@ impl TextProcessor {
                         word_indices: Vec::new(),
                     }
                 });
-                
+
                 entry.count += 1;
                 if !entry.sentences.contains(&sent_idx) {
                     entry.sentences.push(sent_idx);
                 }
                 entry.word_indices.push(word_idx);
+
+                surviving.push(word.clone());
+                surviving_positions.push(word_idx);
             }
         }
+
+        self.generation_tokens.push(surviving);
+        self.generation_token_positions.push(surviving_positions);
     }
     
     fn tokenize_sentence(&self, sentence: &str) -> Vec<String> {
@@ -286,6 +944,133 @@ impl TextProcessor {
             .map(|word| word.to_string())
             .collect()
     }
+
+    // Rewrites raw tokens to a canonical form before they enter word_cache: an optional
+    // adjacent-token join or long-token split against the observed vocabulary, followed by
+    // the user-supplied synonym mapping.
+    fn normalize_tokens(&self, tokens: &[String], vocabulary: &HashSet<&str>) -> Vec<String> {
+        let mut result = Vec::with_capacity(tokens.len());
+        let mut i = 0;
+
+        while i < tokens.len() {
+            let word = &tokens[i];
+
+            // Auto concat: "health" + "care" -> "healthcare" when the joined form is already
+            // attested vocabulary.
+            if self.auto_split_concat && i + 1 < tokens.len() {
+                let next = &tokens[i + 1];
+                let joined = format!("{}{}", word, next);
+                if word.len() <= 8 && next.len() <= 8 && vocabulary.contains(joined.as_str()) {
+                    result.push(self.canonicalize(&joined));
+                    i += 2;
+                    continue;
+                }
+            }
+
+            // Auto split: "healthcare" -> "health" + "care" when the whole word isn't itself
+            // attested but both halves are.
+            if self.auto_split_concat && word.len() > 8 && !vocabulary.contains(word.as_str()) {
+                if let Some((left, right)) = split_against_vocabulary(word, vocabulary) {
+                    result.push(self.canonicalize(&left));
+                    result.push(self.canonicalize(&right));
+                    i += 1;
+                    continue;
+                }
+            }
+
+            result.push(self.canonicalize(word));
+            i += 1;
+        }
+
+        result
+    }
+
+    fn canonicalize(&self, word: &str) -> String {
+        self.synonyms.get(word).cloned().unwrap_or_else(|| word.to_string())
+    }
+
+    // Evaluates a query operation bottom-up, returning the set of matching generation indices.
+    // Terms resolve through word_cache; phrases are matched against generation_tokens.
+    fn eval_operation(&self, op: &Operation) -> HashSet<usize> {
+        match op {
+            Operation::Term(word) => self.word_cache.get(word)
+                .map(|metadata| metadata.sentences.iter().copied().collect())
+                .unwrap_or_default(),
+            Operation::Phrase(words) => self.generation_tokens.iter()
+                .enumerate()
+                .filter(|(_, tokens)| contains_subsequence(tokens, words))
+                .map(|(sent_idx, _)| sent_idx)
+                .collect(),
+            Operation::And(ops) => {
+                let mut sets = ops.iter().map(|op| self.eval_operation(op));
+                match sets.next() {
+                    Some(first) => sets.fold(first, |acc, set| acc.intersection(&set).copied().collect()),
+                    None => HashSet::new(),
+                }
+            }
+            Operation::Or(ops) => ops.iter().fold(HashSet::new(), |mut acc, op| {
+                acc.extend(self.eval_operation(op));
+                acc
+            }),
+        }
+    }
+
+    // Positions within a single generation's surviving token sequence that a query operation
+    // matches, for front-end highlighting.
+    fn match_positions(&self, op: &Operation, sent_idx: usize) -> Vec<usize> {
+        let Some(tokens) = self.generation_tokens.get(sent_idx) else {
+            return Vec::new();
+        };
+
+        match op {
+            Operation::Term(word) => tokens.iter()
+                .enumerate()
+                .filter(|(_, token)| *token == word)
+                .map(|(idx, _)| idx)
+                .collect(),
+            Operation::Phrase(words) => {
+                if words.is_empty() || tokens.len() < words.len() {
+                    return Vec::new();
+                }
+                (0..=tokens.len() - words.len())
+                    .filter(|&start| tokens[start..start + words.len()] == words[..])
+                    .collect()
+            }
+            Operation::And(ops) | Operation::Or(ops) => {
+                let mut positions: Vec<usize> = ops.iter()
+                    .flat_map(|op| self.match_positions(op, sent_idx))
+                    .collect();
+                positions.sort_unstable();
+                positions.dedup();
+                positions
+            }
+        }
+    }
+}
+
+// A phrase matches a generation when its words appear as a contiguous run within that
+// generation's surviving (stop-word-free) token sequence. Stop words and short tokens dropped
+// between two phrase words do not break the run, so the matched words' word_indices need not
+// be consecutive.
+fn contains_subsequence(tokens: &[String], phrase: &[String]) -> bool {
+    if phrase.is_empty() || tokens.len() < phrase.len() {
+        return false;
+    }
+    tokens.windows(phrase.len()).any(|window| window == phrase)
+}
+
+// Finds a split point turning `word` into two attested vocabulary words, if one exists.
+fn split_against_vocabulary(word: &str, vocabulary: &HashSet<&str>) -> Option<(String, String)> {
+    for split_at in 2..word.len().saturating_sub(1) {
+        if !word.is_char_boundary(split_at) {
+            continue;
+        }
+        let (left, right) = word.split_at(split_at);
+        if vocabulary.contains(left) && vocabulary.contains(right) {
+            return Some((left.to_string(), right.to_string()));
+        }
+    }
+    None
 }
 
 // Export utility functions
@@ -315,4 +1100,230 @@ pub fn get_wasm_memory_usage() -> u32 {
     wasm_bindgen::memory()
         .buffer()
         .byte_length() / (64 * 1024)
+}
+
+// Caps the edit distance fuzzy-matching will tolerate for a word of a given length, the way
+// tolerant search engines scale fuzziness with term length, then clamps it to the caller's
+// `max_typo` ceiling.
+fn allowed_typo_distance(word_len: usize, max_typo: usize) -> usize {
+    let length_cap = if word_len <= 4 {
+        0
+    } else if word_len <= 8 {
+        1
+    } else {
+        2
+    };
+    length_cap.min(max_typo)
+}
+
+// Bounded Levenshtein distance using the classic two-row DP, with an early exit once the
+// running minimum of a row exceeds `max_distance` so dissimilar pairs bail out cheaply.
+fn levenshtein_bounded(a: &str, b: &str, max_distance: usize) -> Option<usize> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    if a.len().abs_diff(b.len()) > max_distance {
+        return None;
+    }
+
+    let mut prev_row: Vec<usize> = (0..=b.len()).collect();
+    let mut curr_row = vec![0usize; b.len() + 1];
+
+    for i in 1..=a.len() {
+        curr_row[0] = i;
+        let mut row_min = curr_row[0];
+
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            curr_row[j] = (prev_row[j] + 1)
+                .min(curr_row[j - 1] + 1)
+                .min(prev_row[j - 1] + cost);
+            row_min = row_min.min(curr_row[j]);
+        }
+
+        if row_min > max_distance {
+            return None;
+        }
+
+        std::mem::swap(&mut prev_row, &mut curr_row);
+    }
+
+    let distance = prev_row[b.len()];
+    if distance <= max_distance {
+        Some(distance)
+    } else {
+        None
+    }
+}
+
+// Minimal union-find over dense word indices, used to cluster fuzzy-matched word variants.
+struct UnionFind {
+    parent: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(size: usize) -> Self {
+        UnionFind { parent: (0..size).collect() }
+    }
+
+    fn find(&mut self, x: usize) -> usize {
+        if self.parent[x] != x {
+            self.parent[x] = self.find(self.parent[x]);
+        }
+        self.parent[x]
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (root_a, root_b) = (self.find(a), self.find(b));
+        if root_a != root_b {
+            self.parent[root_a] = root_b;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_bare_term() {
+        let op = parse_query("\"model\"").unwrap();
+        assert_eq!(op, Operation::Term("model".to_string()));
+    }
+
+    #[test]
+    fn parses_a_phrase_literal() {
+        let op = parse_query("\"language model\"").unwrap();
+        assert_eq!(op, Operation::Phrase(vec!["language".to_string(), "model".to_string()]));
+    }
+
+    #[test]
+    fn parses_and_before_or_precedence() {
+        // "a" AND "b" OR "c" should group as Or(And(a, b), c), not And(a, Or(b, c)).
+        let op = parse_query("\"a\" AND \"b\" OR \"c\"").unwrap();
+        assert_eq!(
+            op,
+            Operation::Or(vec![
+                Operation::And(vec![Operation::Term("a".to_string()), Operation::Term("b".to_string())]),
+                Operation::Term("c".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn parses_parenthesized_grouping() {
+        let op = parse_query("(\"model\" AND \"weights\") OR \"training\"").unwrap();
+        assert_eq!(
+            op,
+            Operation::Or(vec![
+                Operation::And(vec![Operation::Term("model".to_string()), Operation::Term("weights".to_string())]),
+                Operation::Term("training".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn rejects_unbalanced_parens() {
+        assert!(parse_query("(\"model\" AND \"weights\"").is_err());
+        assert!(parse_query("\"model\")").is_err());
+    }
+
+    #[test]
+    fn rejects_an_empty_phrase_literal() {
+        // An empty quoted literal has no words at all, so it is an error, not a one-word Term.
+        assert!(parse_query("\"\"").is_err());
+    }
+
+    #[test]
+    fn rejects_bare_unquoted_words() {
+        assert!(parse_query("model AND weights").is_err());
+    }
+
+    #[test]
+    fn contains_subsequence_matches_only_contiguous_runs() {
+        let tokens = vec!["as".to_string(), "an".to_string(), "ai".to_string(), "language".to_string(), "model".to_string()];
+        let phrase = vec!["ai".to_string(), "language".to_string(), "model".to_string()];
+        assert!(contains_subsequence(&tokens, &phrase));
+
+        let non_contiguous = vec!["ai".to_string(), "model".to_string()];
+        assert!(!contains_subsequence(&tokens, &non_contiguous));
+    }
+
+    #[test]
+    fn contains_subsequence_rejects_empty_phrase() {
+        let tokens = vec!["model".to_string()];
+        assert!(!contains_subsequence(&tokens, &[]));
+    }
+
+    #[test]
+    fn allowed_typo_distance_scales_with_length_and_caps_at_max_typo() {
+        assert_eq!(allowed_typo_distance(3, 5), 0);
+        assert_eq!(allowed_typo_distance(8, 5), 1);
+        assert_eq!(allowed_typo_distance(12, 5), 2);
+        assert_eq!(allowed_typo_distance(12, 1), 1);
+    }
+
+    #[test]
+    fn levenshtein_bounded_finds_distance_within_budget() {
+        assert_eq!(levenshtein_bounded("color", "colour", 1), Some(1));
+        assert_eq!(levenshtein_bounded("optimize", "optimise", 1), Some(1));
+        assert_eq!(levenshtein_bounded("kitten", "sitting", 3), Some(3));
+    }
+
+    #[test]
+    fn levenshtein_bounded_bails_out_past_max_distance() {
+        assert_eq!(levenshtein_bounded("color", "banana", 1), None);
+        assert_eq!(levenshtein_bounded("", "abc", 2), None);
+        assert_eq!(levenshtein_bounded("", "", 0), Some(0));
+    }
+
+    #[test]
+    fn merge_variants_clusters_typo_pairs_regardless_of_which_word_is_shorter() {
+        // Drives the real merge_variants traversal directly (not a cleaned-up stand-in of
+        // it), so a regression in its order-dependence would actually fail this test.
+        // "cart"/"carts" and "colr"/"color" each pair a <=4-char word (whose own
+        // allowed_typo_distance is 0) with a longer one-edit-away partner -- previously the
+        // short word never initiated a search and the long word only looked at
+        // higher-HashMap-index candidates, so these pairs merged or not depending on hash
+        // seed alone. "color"/"colour" (both >4 chars) is a sanity baseline that already
+        // merged reliably before the fix.
+        let mut processor = TextProcessor::new();
+        for (word, count) in [
+            ("cart", 3), ("carts", 2),
+            ("colr", 1), ("color", 4), ("colour", 2),
+            ("training", 5),
+        ] {
+            processor.word_cache.insert(word.to_string(), WordMetadata {
+                word: word.to_string(),
+                count,
+                sentences: vec![0],
+                word_indices: vec![0],
+            });
+        }
+        processor.generation_count = 1;
+        processor.generation_tokens = vec![vec![
+            "cart".to_string(), "carts".to_string(),
+            "colr".to_string(), "color".to_string(), "colour".to_string(),
+            "training".to_string(),
+        ]];
+
+        processor.merge_variants(2);
+
+        let cart_group_count: u32 = ["cart", "carts"].iter()
+            .filter_map(|w| processor.word_cache.get(*w))
+            .map(|metadata| metadata.count)
+            .sum();
+        let color_group_count: u32 = ["colr", "color", "colour"].iter()
+            .filter_map(|w| processor.word_cache.get(*w))
+            .map(|metadata| metadata.count)
+            .sum();
+
+        assert_eq!(cart_group_count, 5, "cart and carts should collapse into one entry");
+        assert_eq!(color_group_count, 7, "colr, color and colour should collapse into one entry");
+        assert!(processor.word_cache.contains_key("training"));
+        assert_eq!(
+            processor.word_cache.len(), 3,
+            "expected one surviving entry each for the cart group, the color group, and training"
+        );
+    }
 }
\ No newline at end of file